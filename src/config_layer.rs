@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User overrides for the HGNC symbol lookup table, parsed from an INI-like file.
+///
+/// Modeled on Mercurial's config layering: a file is a sequence of `[alias]`-section entries
+/// (`CUSTOM_NAME = HGNC:1234`), `%include <path>` directives that splice in another file at that
+/// point (paths are resolved relative to the including file), and `%unset <symbol>` directives
+/// that remove whatever mapping precedes them. Entries are applied in file order, so a later
+/// `%unset`/alias always wins over an earlier one for the same symbol.
+#[derive(Debug, Default, Clone)]
+pub struct ConfigLayer {
+    /// Uppercased symbol -> its override, in last-write-wins order. `None` means the symbol was
+    /// `%unset` and should be removed from the built lookup table entirely.
+    edits: HashMap<String, Option<String>>,
+}
+
+impl ConfigLayer {
+    /// Parses `path`, recursively following `%include` directives.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let mut layer = ConfigLayer::default();
+        let mut stack = Vec::new();
+        layer.load_file(path.as_ref(), &mut stack)?;
+        Ok(layer)
+    }
+
+    /// Parses one file, recursing into `%include`s. `stack` holds the canonical path of every
+    /// file currently being parsed (an ancestor of `path`, not merely a file visited earlier),
+    /// so a file legitimately included twice via separate branches (a "diamond" include) is
+    /// fine, while a file that includes itself, directly or transitively, is rejected.
+    fn load_file(&mut self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+        let canonical = fs::canonicalize(path)
+            .map_err(|e| format!("cannot read override file {}: {}", path.display(), e))?;
+        if stack.contains(&canonical) {
+            return Err(format!("%include cycle at {}", path.display()).into());
+        }
+        stack.push(canonical.clone());
+        let result = self.parse_file(path, &canonical, stack);
+        stack.pop();
+        result
+    }
+
+    fn parse_file(
+        &mut self,
+        path: &Path,
+        canonical: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(), Box<dyn Error>> {
+        let contents = fs::read_to_string(canonical)?;
+        let base_dir = canonical.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut section = String::new();
+
+        for (line_no, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(include_path) = line.strip_prefix("%include") {
+                let include_path = include_path.trim();
+                if include_path.is_empty() {
+                    return Err(
+                        format!("{}:{}: %include requires a path", path.display(), line_no + 1).into(),
+                    );
+                }
+                self.load_file(&base_dir.join(include_path), stack)?;
+                continue;
+            }
+
+            if let Some(symbol) = line.strip_prefix("%unset") {
+                let symbol = symbol.trim();
+                if symbol.is_empty() {
+                    return Err(
+                        format!("{}:{}: %unset requires a symbol", path.display(), line_no + 1).into(),
+                    );
+                }
+                self.edits.insert(symbol.to_uppercase(), None);
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                format!(
+                    "{}:{}: expected `key = value`, got `{}`",
+                    path.display(),
+                    line_no + 1,
+                    line
+                )
+            })?;
+
+            if section == "alias" {
+                self.edits
+                    .insert(key.trim().to_uppercase(), Some(value.trim().to_string()));
+            } else {
+                return Err(format!(
+                    "{}:{}: unknown section `[{}]`",
+                    path.display(),
+                    line_no + 1,
+                    section
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every override in this layer: an uppercased symbol paired with either the HGNC ID it
+    /// should resolve to, or `None` if the symbol was `%unset` and should be dropped.
+    pub fn edits(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.edits
+            .iter()
+            .map(|(symbol, target)| (symbol.as_str(), target.as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap as StdHashMap;
+
+    /// A scratch directory under the system temp dir, cleaned up on drop.
+    struct ScratchDir {
+        path: PathBuf,
+    }
+
+    impl ScratchDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("hgnc_lookup_config_layer_test_{name}"));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            ScratchDir { path }
+        }
+
+        fn write(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.path.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn edits_map(layer: &ConfigLayer) -> StdHashMap<String, Option<String>> {
+        layer
+            .edits()
+            .map(|(symbol, target)| (symbol.to_string(), target.map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn parses_alias_and_unset() {
+        let dir = ScratchDir::new("parses_alias_and_unset");
+        let path = dir.write(
+            "main.conf",
+            "[alias]\nfoo = HGNC:1\n%unset bar\nbaz = HGNC:2\n",
+        );
+
+        let layer = ConfigLayer::load(&path).unwrap();
+        let edits = edits_map(&layer);
+
+        assert_eq!(edits.get("FOO"), Some(&Some("HGNC:1".to_string())));
+        assert_eq!(edits.get("BAZ"), Some(&Some("HGNC:2".to_string())));
+        assert_eq!(edits.get("BAR"), Some(&None));
+    }
+
+    #[test]
+    fn include_merges_child_file_edits() {
+        let dir = ScratchDir::new("include_merges_child_file_edits");
+        dir.write("child.conf", "[alias]\nchild_sym = HGNC:9\n");
+        let path = dir.write(
+            "main.conf",
+            "[alias]\nparent_sym = HGNC:1\n%include child.conf\n",
+        );
+
+        let layer = ConfigLayer::load(&path).unwrap();
+        let edits = edits_map(&layer);
+
+        assert_eq!(edits.get("PARENT_SYM"), Some(&Some("HGNC:1".to_string())));
+        assert_eq!(edits.get("CHILD_SYM"), Some(&Some("HGNC:9".to_string())));
+    }
+
+    #[test]
+    fn diamond_include_is_not_a_cycle() {
+        // top includes both left and right, which both include shared.conf. shared.conf is a
+        // true diamond: it's visited twice via non-overlapping branches, not via itself.
+        let dir = ScratchDir::new("diamond_include_is_not_a_cycle");
+        dir.write("shared.conf", "[alias]\nshared_sym = HGNC:1\n");
+        dir.write("left.conf", "%include shared.conf\n");
+        dir.write("right.conf", "%include shared.conf\n");
+        let path = dir.write("top.conf", "%include left.conf\n%include right.conf\n");
+
+        let layer = ConfigLayer::load(&path).unwrap();
+        let edits = edits_map(&layer);
+
+        assert_eq!(edits.get("SHARED_SYM"), Some(&Some("HGNC:1".to_string())));
+    }
+
+    #[test]
+    fn true_include_cycle_is_rejected() {
+        let dir = ScratchDir::new("true_include_cycle_is_rejected");
+        let path = dir.write("a.conf", "%include b.conf\n");
+        dir.write("b.conf", "%include a.conf\n");
+
+        let result = ConfigLayer::load(&path);
+        assert!(result.is_err());
+    }
+}
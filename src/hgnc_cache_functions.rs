@@ -1,3 +1,4 @@
+use crate::config_layer::ConfigLayer;
 use crate::hgnc_struct::{ArchivedHgncCache, HgncCache, HgncRecord};
 use rkyv::rancor;
 
@@ -11,23 +12,430 @@ use std::{
 const HGNC_COMPLETE_SET_URL: &str =
     "https://storage.googleapis.com/public-download-files/hgnc/tsv/tsv/hgnc_complete_set.txt";
 
-/// Resolve ~/.cache/hgnc_lookup/hgnc_complete_set.bin (Linux) using a best-effort approach.
-pub fn get_hgnc_bin_cache_path() -> Result<PathBuf, Box<dyn Error>> {
+/// Magic tag identifying an `hgnc_lookup` cache file.
+const CACHE_MAGIC: &[u8; 8] = b"HGNCLKUP";
+
+/// Bumped whenever the `HgncRecord`/`HgncCache` layout (or the docket header itself) changes
+/// in a way that isn't compatible with previously dumped cache files.
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// Fixed width reserved for the HGNC source release date string in the docket header.
+const RELEASE_DATE_LEN: usize = 32;
+
+/// Fixed width reserved for the HTTP `Last-Modified` validator in the docket header.
+const LAST_MODIFIED_LEN: usize = 40;
+
+/// Fixed width reserved for the HTTP `ETag` validator in the docket header.
+const ETAG_LEN: usize = 80;
+
+/// Length of the BLAKE3 content hash stored in the docket header.
+const CONTENT_HASH_LEN: usize = 32;
+
+/// Trailing padding so `DOCKET_LEN` comes out 16-byte aligned (see the `DOCKET_LEN` assertion
+/// below); left as zero bytes by `CacheDocket::encode` and otherwise unused.
+const RESERVED_LEN: usize = 12;
+
+/// Total size of the docket header prepended to the rkyv-serialized cache body.
+const DOCKET_LEN: usize = CACHE_MAGIC.len()
+    + 4
+    + RELEASE_DATE_LEN
+    + LAST_MODIFIED_LEN
+    + ETAG_LEN
+    + CONTENT_HASH_LEN
+    + RESERVED_LEN;
+
+// `rkyv::access` requires the archived body to start at an alignment matching its most-aligned
+// field; 16 bytes covers every width `rkyv` targets (32- and 64-bit pointers alike). Since the
+// body is written and mapped starting right at `DOCKET_LEN`, the docket itself must be a
+// multiple of 16, not just a multiple of its largest individual field.
+const _: () = assert!(
+    DOCKET_LEN.is_multiple_of(16),
+    "DOCKET_LEN must be 16-byte aligned for rkyv::access over the mapped cache body"
+);
+
+/// HTTP cache validators describing the HGNC release a cache file was built from.
+///
+/// `last_modified`/`etag` are empty when the source didn't provide them (e.g. a cache built
+/// from a local `--set-file`).
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseValidators {
+    pub release_date: String,
+    pub last_modified: String,
+    pub etag: String,
+}
+
+/// Writes `value` left-aligned into `buf`, truncating if it doesn't fit.
+fn encode_fixed_str(buf: &mut [u8], value: &str) {
+    let bytes = value.as_bytes();
+    let len = bytes.len().min(buf.len());
+    buf[..len].copy_from_slice(&bytes[..len]);
+}
+
+/// Reads a NUL-padded string previously written by `encode_fixed_str`.
+fn decode_fixed_str(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+    Ok(String::from_utf8(
+        bytes.iter().copied().take_while(|&b| b != 0).collect(),
+    )?)
+}
+
+/// Fixed-size header written ahead of the rkyv-serialized cache body.
+///
+/// Validating this on load lets us reject a cache written by an incompatible build, or one
+/// that's truncated/corrupt, with a descriptive error instead of panicking inside `rkyv::access`.
+struct CacheDocket {
+    format_version: u32,
+    validators: ReleaseValidators,
+    content_hash: [u8; CONTENT_HASH_LEN],
+}
+
+impl CacheDocket {
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; DOCKET_LEN];
+        let mut offset = 0;
+
+        buf[offset..offset + CACHE_MAGIC.len()].copy_from_slice(CACHE_MAGIC);
+        offset += CACHE_MAGIC.len();
+
+        buf[offset..offset + 4].copy_from_slice(&self.format_version.to_le_bytes());
+        offset += 4;
+
+        encode_fixed_str(
+            &mut buf[offset..offset + RELEASE_DATE_LEN],
+            &self.validators.release_date,
+        );
+        offset += RELEASE_DATE_LEN;
+
+        encode_fixed_str(
+            &mut buf[offset..offset + LAST_MODIFIED_LEN],
+            &self.validators.last_modified,
+        );
+        offset += LAST_MODIFIED_LEN;
+
+        encode_fixed_str(&mut buf[offset..offset + ETAG_LEN], &self.validators.etag);
+        offset += ETAG_LEN;
+
+        buf[offset..offset + CONTENT_HASH_LEN].copy_from_slice(&self.content_hash);
+
+        buf
+    }
+
+    /// Splits `bytes` into a validated docket and the remaining cache body.
+    fn decode(bytes: &[u8]) -> Result<(Self, &[u8]), Box<dyn Error>> {
+        if bytes.len() < DOCKET_LEN {
+            return Err("Cache file is truncated: missing docket header".into());
+        }
+
+        let (header, body) = bytes.split_at(DOCKET_LEN);
+        let mut offset = 0;
+
+        let magic = &header[offset..offset + CACHE_MAGIC.len()];
+        if magic != CACHE_MAGIC {
+            return Err("Cache file has an invalid magic tag".into());
+        }
+        offset += CACHE_MAGIC.len();
+
+        let format_version = u32::from_le_bytes(header[offset..offset + 4].try_into()?);
+        offset += 4;
+        if format_version != CACHE_FORMAT_VERSION {
+            return Err(format!(
+                "Cache file format version {} is incompatible with expected version {}",
+                format_version, CACHE_FORMAT_VERSION
+            )
+            .into());
+        }
+
+        let release_date = decode_fixed_str(&header[offset..offset + RELEASE_DATE_LEN])?;
+        offset += RELEASE_DATE_LEN;
+
+        let last_modified = decode_fixed_str(&header[offset..offset + LAST_MODIFIED_LEN])?;
+        offset += LAST_MODIFIED_LEN;
+
+        let etag = decode_fixed_str(&header[offset..offset + ETAG_LEN])?;
+        offset += ETAG_LEN;
+
+        let mut content_hash = [0u8; CONTENT_HASH_LEN];
+        content_hash.copy_from_slice(&header[offset..offset + CONTENT_HASH_LEN]);
+
+        Ok((
+            CacheDocket {
+                format_version,
+                validators: ReleaseValidators {
+                    release_date,
+                    last_modified,
+                    etag,
+                },
+                content_hash,
+            },
+            body,
+        ))
+    }
+}
+
+/// Filename prefix/suffix bracketing the release date in a dated cache file, e.g.
+/// `hgnc_complete_set.2024-06-01.bin`.
+const HGNC_BIN_PREFIX: &str = "hgnc_complete_set.";
+const HGNC_BIN_SUFFIX: &str = ".bin";
+
+/// Resolve ~/.cache/hgnc_lookup (Linux) using a best-effort approach.
+pub fn get_hgnc_cache_dir() -> Result<PathBuf, Box<dyn Error>> {
     let base_cache = dirs::cache_dir().ok_or("Could not determine user cache directory")?;
-    Ok(base_cache.join("hgnc_lookup").join("hgnc_complete_set.bin"))
+    Ok(base_cache.join("hgnc_lookup"))
+}
+
+/// Resolve the cache file path for a specific HGNC release, e.g. `2024-06-01`.
+pub fn get_hgnc_bin_cache_path_for_release(release_date: &str) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(get_hgnc_cache_dir()?.join(format!("{HGNC_BIN_PREFIX}{release_date}{HGNC_BIN_SUFFIX}")))
+}
+
+/// One cached HGNC release on disk.
+#[derive(Debug, Clone)]
+pub struct CachedRelease {
+    pub release_date: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub modified: std::time::SystemTime,
 }
 
-/// Ensure the parent directory exists.
-fn ensure_parent_dir(path: &Path) -> Result<(), Box<dyn Error>> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+/// Lists every cached HGNC release found in the cache directory, most recently modified first.
+pub fn list_cached_releases() -> Result<Vec<CachedRelease>, Box<dyn Error>> {
+    let dir = get_hgnc_cache_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
     }
+
+    let mut releases = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let Some(release_date) = file_name
+            .strip_prefix(HGNC_BIN_PREFIX)
+            .and_then(|s| s.strip_suffix(HGNC_BIN_SUFFIX))
+        else {
+            continue;
+        };
+
+        let metadata = entry.metadata()?;
+        releases.push(CachedRelease {
+            release_date: release_date.to_string(),
+            path: entry.path(),
+            size_bytes: metadata.len(),
+            modified: metadata.modified()?,
+        });
+    }
+
+    releases.sort_by_key(|r| std::cmp::Reverse(r.modified));
+    Ok(releases)
+}
+
+/// Sort key used when pruning a subset of cached releases.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum PruneSort {
+    Oldest,
+    Largest,
+    Alpha,
+}
+
+/// Which cached releases a `prune` pass should target.
+#[derive(Debug)]
+pub enum PruneScope {
+    All,
+    Group {
+        sort: PruneSort,
+        n: usize,
+        invert: bool,
+    },
+}
+
+/// Picks which of `releases` a prune pass targets, without touching the filesystem.
+fn select_releases_for_scope(
+    mut releases: Vec<CachedRelease>,
+    scope: &PruneScope,
+) -> Vec<CachedRelease> {
+    match scope {
+        PruneScope::All => releases,
+        PruneScope::Group { sort, n, invert } => {
+            match sort {
+                PruneSort::Oldest => releases.sort_by_key(|r| r.modified),
+                PruneSort::Largest => releases.sort_by_key(|r| std::cmp::Reverse(r.size_bytes)),
+                PruneSort::Alpha => releases.sort_by(|a, b| a.release_date.cmp(&b.release_date)),
+            }
+            if *invert {
+                releases.reverse();
+            }
+            releases.into_iter().take(*n).collect()
+        }
+    }
+}
+
+/// Deletes cached releases matching `scope`, returning the ones removed.
+pub fn prune_cached_releases(scope: &PruneScope) -> Result<Vec<CachedRelease>, Box<dyn Error>> {
+    let releases = list_cached_releases()?;
+    let to_remove = select_releases_for_scope(releases, scope);
+
+    for release in &to_remove {
+        std::fs::remove_file(&release.path)?;
+    }
+
+    Ok(to_remove)
+}
+
+/// Fallback release identifier for a cache whose upstream release date can't be determined
+/// (e.g. no `Last-Modified` header, or a local `--set-file`): the date it was built locally.
+fn local_build_date() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Derives a release date (`%Y-%m-%d`) from an HTTP `Last-Modified` header value, so two
+/// different upstream releases downloaded on the same day still get distinct cache filenames.
+/// Returns `None` if `last_modified` is empty or not a valid HTTP date.
+fn release_date_from_last_modified(last_modified: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc2822(last_modified)
+        .ok()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+}
+
+/// Linux's `statfs` magic number for NFS mounts (see `statfs(2)`).
+#[cfg(target_os = "linux")]
+const NFS_SUPER_MAGIC: i64 = 0x6969;
+
+/// Returns `true` if `path` lives on an NFS mount, best-effort.
+///
+/// Memory-mapping a file on NFS can stall or raise `SIGBUS` if the file is truncated or
+/// revalidated out from under the mapping, so callers should fall back to a regular read
+/// in that case. Any error probing the mount is treated as "not NFS" rather than failing
+/// the caller.
+#[cfg(target_os = "linux")]
+fn is_nfs_mount(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+
+    let mut stat = MaybeUninit::<libc::statfs>::uninit();
+    let ret = unsafe { libc::statfs(c_path.as_ptr(), stat.as_mut_ptr()) };
+    if ret != 0 {
+        return false;
+    }
+
+    let stat = unsafe { stat.assume_init() };
+    // `f_type`'s exact integer type varies across libc targets.
+    #[allow(clippy::unnecessary_cast)]
+    {
+        stat.f_type as i64 == NFS_SUPER_MAGIC
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_nfs_mount(_path: &Path) -> bool {
+    false
+}
+
+/// Maps `path` into memory and leaks the mapping for the program's lifetime, falling back to a
+/// full read into an owned, leaked buffer when `path` is on an NFS mount.
+fn open_cache_bytes(path: &Path) -> Result<&'static [u8], Box<dyn Error>> {
+    if is_nfs_mount(path) {
+        let bytes = std::fs::read(path)?;
+        return Ok(Box::leak(bytes.into_boxed_slice()));
+    }
+
+    let file = File::open(path)?;
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    let mmap: &'static memmap2::Mmap = Box::leak(Box::new(mmap));
+    Ok(mmap.as_ref())
+}
+
+/// Assigns each distinct field value a stable `u32` id the first time it's seen, so the cache
+/// can store one copy of a repeated value (e.g. `status`, `locus_group`) instead of one per
+/// record. Id `0` is reserved for the empty string.
+struct StringInterner {
+    ids: std::collections::HashMap<String, u32>,
+    pool: Vec<String>,
+}
+
+impl StringInterner {
+    fn new() -> Self {
+        StringInterner {
+            ids: std::collections::HashMap::from([(String::new(), 0)]),
+            pool: vec![String::new()],
+        }
+    }
+
+    fn intern(&mut self, value: String) -> u32 {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+        let id = self.pool.len() as u32;
+        self.ids.insert(value.clone(), id);
+        self.pool.push(value);
+        id
+    }
+}
+
+/// Adds `record_idx` to `key`'s lookup entry, unless it's already present.
+///
+/// A record's own approved symbol can reappear in its `alias_symbol`/`prev_symbol` columns
+/// (and aliases can repeat), which would otherwise push the same index twice and make
+/// `query_lookup_table` report an `AmbiguousMatch` against a single record.
+fn add_lookup_entry(
+    lookup: &mut std::collections::HashMap<String, Vec<usize>>,
+    key: String,
+    record_idx: usize,
+) {
+    let entries = lookup.entry(key).or_default();
+    if !entries.contains(&record_idx) {
+        entries.push(record_idx);
+    }
+}
+
+/// Resolves each [`ConfigLayer`] edit against `records`/`string_pool` and applies it to `lookup`:
+/// an alias override collapses the symbol to that single record (resolving any ambiguity), and
+/// an `%unset` override removes the symbol entirely.
+fn apply_config_overrides(
+    records: &[HgncRecord],
+    string_pool: &[String],
+    lookup: &mut std::collections::HashMap<String, Vec<usize>>,
+    layer: &ConfigLayer,
+) -> Result<(), Box<dyn Error>> {
+    let index_by_hgnc_id: std::collections::HashMap<&str, usize> = records
+        .iter()
+        .enumerate()
+        .map(|(idx, record)| (string_pool[record.hgnc_id as usize].as_str(), idx))
+        .collect();
+
+    for (symbol, target) in layer.edits() {
+        match target {
+            Some(hgnc_id) => {
+                let &idx = index_by_hgnc_id.get(hgnc_id).ok_or_else(|| {
+                    format!(
+                        "override alias '{}' references unknown HGNC ID '{}'",
+                        symbol, hgnc_id
+                    )
+                })?;
+                lookup.insert(symbol.to_string(), vec![idx]);
+            }
+            None => {
+                lookup.remove(symbol);
+            }
+        }
+    }
+
     Ok(())
 }
 
 /// Loads HGNC data from a tab-delimited file into an HgncCache
 /// All mappings point to the index of the record in the records vector.
-pub fn create_hgnc_cache_from_reader<R: BufRead>(reader: R) -> Result<HgncCache, Box<dyn Error>> {
+///
+/// `overrides`, if given, is merged over the lookup table built from `reader` (see
+/// [`apply_config_overrides`]).
+pub fn create_hgnc_cache_from_reader<R: BufRead>(
+    reader: R,
+    overrides: Option<&ConfigLayer>,
+) -> Result<HgncCache, Box<dyn Error>> {
     let mut lines = reader.lines();
 
     // Read and parse header
@@ -40,10 +448,9 @@ pub fn create_hgnc_cache_from_reader<R: BufRead>(reader: R) -> Result<HgncCache,
         col_map.insert(*header, i);
     }
 
-    let mut cache = HgncCache {
-        records: Vec::new(),
-        lookup: std::collections::HashMap::new(),
-    };
+    let mut records = Vec::new();
+    let mut lookup = std::collections::HashMap::new();
+    let mut interner = StringInterner::new();
 
     // Process each data line
     for line_result in lines {
@@ -59,159 +466,335 @@ pub fn create_hgnc_cache_from_reader<R: BufRead>(reader: R) -> Result<HgncCache,
                 .to_string()
         };
 
-        // Create HgncRecord from fields
+        // Symbol/alias/prev-symbol are needed in raw form for the lookup table below, in
+        // addition to being interned into the record like every other field.
+        let symbol = get_field("symbol");
+        let alias_symbol = get_field("alias_symbol");
+        let prev_symbol = get_field("prev_symbol");
+
+        // Create HgncRecord from interned field values
         let record = HgncRecord {
-            hgnc_id: get_field("hgnc_id"),
-            symbol: get_field("symbol"),
-            name: get_field("name"),
-            locus_group: get_field("locus_group"),
-            locus_type: get_field("locus_type"),
-            status: get_field("status"),
-            location: get_field("location"),
-            location_sortable: get_field("location_sortable"),
-            alias_symbol: get_field("alias_symbol"),
-            alias_name: get_field("alias_name"),
-            prev_symbol: get_field("prev_symbol"),
-            prev_name: get_field("prev_name"),
-            gene_group: get_field("gene_group"),
-            gene_group_id: get_field("gene_group_id"),
-            date_approved_reserved: get_field("date_approved_reserved"),
-            date_symbol_changed: get_field("date_symbol_changed"),
-            date_name_changed: get_field("date_name_changed"),
-            date_modified: get_field("date_modified"),
-            entrez_id: get_field("entrez_id"),
-            ensembl_gene_id: get_field("ensembl_gene_id"),
-            vega_id: get_field("vega_id"),
-            ucsc_id: get_field("ucsc_id"),
-            ena: get_field("ena"),
-            refseq_accession: get_field("refseq_accession"),
-            ccds_id: get_field("ccds_id"),
-            uniprot_ids: get_field("uniprot_ids"),
-            pubmed_id: get_field("pubmed_id"),
-            mgd_id: get_field("mgd_id"),
-            rgd_id: get_field("rgd_id"),
-            lsdb: get_field("lsdb"),
-            cosmic: get_field("cosmic"),
-            omim_id: get_field("omim_id"),
-            mirbase: get_field("mirbase"),
-            homeodb: get_field("homeodb"),
-            snornabase: get_field("snornabase"),
-            bioparadigms_slc: get_field("bioparadigms_slc"),
-            orphanet: get_field("orphanet"),
-            pseudogene_org: get_field("pseudogene.org"),
-            horde_id: get_field("horde_id"),
-            merops: get_field("merops"),
-            imgt: get_field("imgt"),
-            iuphar: get_field("iuphar"),
-            kznf_gene_catalog: get_field("kznf_gene_catalog"),
-            mamit_trnadb: get_field("mamit-trnadb"),
-            cd: get_field("cd"),
-            lncrnadb: get_field("lncrnadb"),
-            enzyme_id: get_field("enzyme_id"),
-            intermediate_filament_db: get_field("intermediate_filament_db"),
-            rna_central_id: get_field("rna_central_id"),
-            lncipedia: get_field("lncipedia"),
-            gtrnadb: get_field("gtrnadb"),
-            agr: get_field("agr"),
-            mane_select: get_field("mane_select"),
-            gencc: get_field("gencc"),
+            hgnc_id: interner.intern(get_field("hgnc_id")),
+            symbol: interner.intern(symbol.clone()),
+            name: interner.intern(get_field("name")),
+            locus_group: interner.intern(get_field("locus_group")),
+            locus_type: interner.intern(get_field("locus_type")),
+            status: interner.intern(get_field("status")),
+            location: interner.intern(get_field("location")),
+            location_sortable: interner.intern(get_field("location_sortable")),
+            alias_symbol: interner.intern(alias_symbol.clone()),
+            alias_name: interner.intern(get_field("alias_name")),
+            prev_symbol: interner.intern(prev_symbol.clone()),
+            prev_name: interner.intern(get_field("prev_name")),
+            gene_group: interner.intern(get_field("gene_group")),
+            gene_group_id: interner.intern(get_field("gene_group_id")),
+            date_approved_reserved: interner.intern(get_field("date_approved_reserved")),
+            date_symbol_changed: interner.intern(get_field("date_symbol_changed")),
+            date_name_changed: interner.intern(get_field("date_name_changed")),
+            date_modified: interner.intern(get_field("date_modified")),
+            entrez_id: interner.intern(get_field("entrez_id")),
+            ensembl_gene_id: interner.intern(get_field("ensembl_gene_id")),
+            vega_id: interner.intern(get_field("vega_id")),
+            ucsc_id: interner.intern(get_field("ucsc_id")),
+            ena: interner.intern(get_field("ena")),
+            refseq_accession: interner.intern(get_field("refseq_accession")),
+            ccds_id: interner.intern(get_field("ccds_id")),
+            uniprot_ids: interner.intern(get_field("uniprot_ids")),
+            pubmed_id: interner.intern(get_field("pubmed_id")),
+            mgd_id: interner.intern(get_field("mgd_id")),
+            rgd_id: interner.intern(get_field("rgd_id")),
+            lsdb: interner.intern(get_field("lsdb")),
+            cosmic: interner.intern(get_field("cosmic")),
+            omim_id: interner.intern(get_field("omim_id")),
+            mirbase: interner.intern(get_field("mirbase")),
+            homeodb: interner.intern(get_field("homeodb")),
+            snornabase: interner.intern(get_field("snornabase")),
+            bioparadigms_slc: interner.intern(get_field("bioparadigms_slc")),
+            orphanet: interner.intern(get_field("orphanet")),
+            pseudogene_org: interner.intern(get_field("pseudogene.org")),
+            horde_id: interner.intern(get_field("horde_id")),
+            merops: interner.intern(get_field("merops")),
+            imgt: interner.intern(get_field("imgt")),
+            iuphar: interner.intern(get_field("iuphar")),
+            kznf_gene_catalog: interner.intern(get_field("kznf_gene_catalog")),
+            mamit_trnadb: interner.intern(get_field("mamit-trnadb")),
+            cd: interner.intern(get_field("cd")),
+            lncrnadb: interner.intern(get_field("lncrnadb")),
+            enzyme_id: interner.intern(get_field("enzyme_id")),
+            intermediate_filament_db: interner.intern(get_field("intermediate_filament_db")),
+            rna_central_id: interner.intern(get_field("rna_central_id")),
+            lncipedia: interner.intern(get_field("lncipedia")),
+            gtrnadb: interner.intern(get_field("gtrnadb")),
+            agr: interner.intern(get_field("agr")),
+            mane_select: interner.intern(get_field("mane_select")),
+            gencc: interner.intern(get_field("gencc")),
         };
 
         // Get the index where this record will be stored
-        let record_idx = cache.records.len();
+        let record_idx = records.len();
 
-        // Add mappings to lookup
+        // Add mappings to lookup. A symbol claimed by more than one record (common with
+        // alias/previous symbols) accumulates every candidate rather than overwriting, so
+        // `query_lookup_table` can report the ambiguity instead of silently picking one.
 
         // 1. HGNC symbol
-        cache
-            .lookup
-            .insert(record.symbol.to_uppercase(), record_idx);
+        add_lookup_entry(&mut lookup, symbol.to_uppercase(), record_idx);
 
         // 2. Alias symbols (pipe-delimited)
-        if !record.alias_symbol.is_empty() {
-            for alias in record.alias_symbol.split('|').filter(|s| !s.is_empty()) {
-                cache.lookup.insert(alias.trim().to_uppercase(), record_idx);
+        if !alias_symbol.is_empty() {
+            for alias in alias_symbol.split('|').filter(|s| !s.is_empty()) {
+                add_lookup_entry(&mut lookup, alias.trim().to_uppercase(), record_idx);
             }
         }
 
         // 3. Previous symbols (pipe-delimited)
-        if !record.prev_symbol.is_empty() {
-            for prev in record.prev_symbol.split('|').filter(|s| !s.is_empty()) {
-                cache.lookup.insert(prev.trim().to_uppercase(), record_idx);
+        if !prev_symbol.is_empty() {
+            for prev in prev_symbol.split('|').filter(|s| !s.is_empty()) {
+                add_lookup_entry(&mut lookup, prev.trim().to_uppercase(), record_idx);
             }
         }
 
         // Add the record to the cache
-        cache.records.push(record);
+        records.push(record);
     }
 
-    Ok(cache)
+    if let Some(layer) = overrides {
+        apply_config_overrides(&records, &interner.pool, &mut lookup, layer)?;
+    }
+
+    Ok(HgncCache {
+        records,
+        lookup,
+        string_pool: interner.pool,
+    })
 }
 
-pub fn create_hgnc_cache<P: AsRef<Path>>(file_path: P) -> Result<HgncCache, Box<dyn Error>> {
+pub fn create_hgnc_cache<P: AsRef<Path>>(
+    file_path: P,
+    overrides: Option<&ConfigLayer>,
+) -> Result<HgncCache, Box<dyn Error>> {
     let file = File::open(file_path)?;
     let reader = BufReader::new(file);
-    create_hgnc_cache_from_reader(reader)
+    create_hgnc_cache_from_reader(reader, overrides)
+}
+
+/// The HGNC complete set body plus whatever cache validators the server returned with it.
+struct DownloadedSet {
+    bytes: Vec<u8>,
+    validators: RemoteValidators,
+}
+
+/// `Last-Modified`/`ETag` as currently reported by the HGNC download server.
+struct RemoteValidators {
+    last_modified: String,
+    etag: String,
 }
 
-fn download_hgnc_complete_set_bytes() -> Result<Vec<u8>, Box<dyn Error>> {
+fn remote_validators_from_headers(headers: &reqwest::header::HeaderMap) -> RemoteValidators {
+    let header_str = |name: reqwest::header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    RemoteValidators {
+        last_modified: header_str(reqwest::header::LAST_MODIFIED),
+        etag: header_str(reqwest::header::ETAG),
+    }
+}
+
+fn download_hgnc_complete_set_bytes() -> Result<DownloadedSet, Box<dyn Error>> {
     // blocking client keeps integration simple for a CLI / library call
     let resp = reqwest::blocking::get(HGNC_COMPLETE_SET_URL)?;
     if !resp.status().is_success() {
         return Err(format!("HGNC download failed: HTTP {}", resp.status()).into());
     }
+    let validators = remote_validators_from_headers(resp.headers());
     let bytes = resp.bytes()?;
-    Ok(bytes.to_vec())
+    Ok(DownloadedSet {
+        bytes: bytes.to_vec(),
+        validators,
+    })
+}
+
+/// Issues a `HEAD` request against `HGNC_COMPLETE_SET_URL` to read its current validators
+/// without downloading the body.
+fn head_hgnc_complete_set_validators() -> Result<RemoteValidators, Box<dyn Error>> {
+    let client = reqwest::blocking::Client::new();
+    let resp = client.head(HGNC_COMPLETE_SET_URL).send()?;
+    if !resp.status().is_success() {
+        return Err(format!("HGNC HEAD request failed: HTTP {}", resp.status()).into());
+    }
+    Ok(remote_validators_from_headers(resp.headers()))
+}
+
+/// Reads just the docket header of an on-disk cache file, without mapping or hash-checking
+/// the body. Used to compare a cached release's validators against the remote before deciding
+/// whether to reuse it.
+fn peek_cache_docket<P: AsRef<Path>>(path: P) -> Result<ReleaseValidators, Box<dyn Error>> {
+    use std::io::Read;
+
+    let mut file = File::open(path)?;
+    let mut header = vec![0u8; DOCKET_LEN];
+    file.read_exact(&mut header)?;
+    let (docket, _) = CacheDocket::decode(&header)?;
+    Ok(docket.validators)
+}
+
+/// Decides whether `latest` can be reused as-is, given `--max-age`/`--offline` policy.
+///
+/// A cache older than `max_age` is treated as stale without contacting the server at all. Short
+/// of that, the cache is only considered stale if a `HEAD` request shows the remote's
+/// `Last-Modified`/`ETag` no longer match what the cache was built from. `offline` skips the
+/// `HEAD` check and trusts any cache that `max_age` has not already marked stale.
+///
+/// A failure to reach the remote (offline without `--offline`, DNS blip, non-2xx from the CDN)
+/// or to peek the local docket is not treated as staleness: only a definitive "remote is newer"
+/// should force a rebuild, so this logs a warning and falls back to reusing `latest`.
+fn is_cache_fresh(latest: &CachedRelease, max_age: Option<std::time::Duration>, offline: bool) -> bool {
+    if let Some(max_age) = max_age {
+        let age = latest.modified.elapsed().unwrap_or_default();
+        if age > max_age {
+            println!(
+                "Cached release {} is older than --max-age ({:?} old); rebuilding.",
+                latest.release_date, age
+            );
+            return false;
+        }
+    }
+
+    if offline {
+        return true;
+    }
+
+    let freshness = peek_cache_docket(&latest.path).and_then(|cached| {
+        let remote = head_hgnc_complete_set_validators()?;
+        Ok(cached.last_modified == remote.last_modified && cached.etag == remote.etag)
+    });
+
+    match freshness {
+        Ok(fresh) => {
+            if !fresh {
+                println!(
+                    "Remote HGNC complete set has changed since release {}; rebuilding.",
+                    latest.release_date
+                );
+            }
+            fresh
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not check HGNC remote for staleness ({}); reusing cached release {}.",
+                e, latest.release_date
+            );
+            true
+        }
+    }
 }
 
 pub fn dump_hgnc_cache<P: AsRef<Path>>(
     cache: &HgncCache,
+    validators: &ReleaseValidators,
     output_path: P,
 ) -> Result<(), Box<dyn Error>> {
-    let _bytes = rkyv::to_bytes::<rancor::Error>(cache).unwrap();
-    std::fs::write(output_path, _bytes)?;
+    let body = rkyv::to_bytes::<rancor::Error>(cache)
+        .map_err(|e| format!("failed to serialize HGNC cache: {}", e))?;
+    let content_hash = *blake3::hash(&body).as_bytes();
+
+    let docket = CacheDocket {
+        format_version: CACHE_FORMAT_VERSION,
+        validators: validators.clone(),
+        content_hash,
+    };
+
+    let mut bytes = docket.encode();
+    bytes.extend_from_slice(&body);
+    std::fs::write(output_path, bytes)?;
     Ok(())
 }
 
 pub fn load_hgnc_cache<P: AsRef<Path>>(
     input_path: P,
 ) -> Result<&'static ArchivedHgncCache, Box<dyn Error>> {
-    let bytes = std::fs::read(input_path)?;
-    let leaked_bytes = Box::leak(bytes.into_boxed_slice());
-    let archived = rkyv::access::<ArchivedHgncCache, rancor::Error>(leaked_bytes).unwrap();
+    let leaked_bytes = open_cache_bytes(input_path.as_ref())?;
+
+    let (docket, body) = CacheDocket::decode(leaked_bytes)?;
+
+    let content_hash = *blake3::hash(body).as_bytes();
+    if content_hash != docket.content_hash {
+        return Err("Cache file failed integrity check: content hash mismatch".into());
+    }
+
+    println!(
+        "Cache docket: release {}, format v{}",
+        docket.validators.release_date, docket.format_version
+    );
+
+    let archived = rkyv::access::<ArchivedHgncCache, rancor::Error>(body)
+        .map_err(|e| format!("Cache file is corrupt: {}", e))?;
     Ok(archived)
 }
 
 pub fn get_hgnc_cache<P: AsRef<Path>>(
     path: Option<P>,
+    force_rebuild: bool,
+    max_age: Option<std::time::Duration>,
+    offline: bool,
+    override_file: Option<&Path>,
 ) -> Result<&'static ArchivedHgncCache, Box<dyn Error>> {
-    let bin_path = get_hgnc_bin_cache_path()?;
-    ensure_parent_dir(&bin_path)?;
+    std::fs::create_dir_all(get_hgnc_cache_dir()?)?;
+
+    let overrides = override_file.map(ConfigLayer::load).transpose()?;
 
-    let cache: HgncCache = match path {
+    let (cache, validators): (HgncCache, ReleaseValidators) = match path {
         Some(p) => {
-            // If file path is provided, create cache and dump to bin_path; will overwrite existing cache
+            // If a file path is provided, always (re)build the cache from it.
             println!("Creating HGNC cache from text file: {:?}", p.as_ref());
-            create_hgnc_cache(p)?
+            let validators = ReleaseValidators {
+                release_date: local_build_date(),
+                ..Default::default()
+            };
+            (create_hgnc_cache(p, overrides.as_ref())?, validators)
         }
         None => {
-            // Check if cache file exists
-            // If it does, we can load it directly
-            if bin_path.exists() {
-                println!("HGNC cache file found at {:?}, loading directly.", bin_path);
-                return load_hgnc_cache(&bin_path);
+            // Reuse the most recently cached release unless it's stale or a rebuild was requested.
+            if !force_rebuild {
+                if let Some(latest) = list_cached_releases()?.into_iter().next() {
+                    if is_cache_fresh(&latest, max_age, offline) {
+                        println!("HGNC cache file found at {:?}, loading directly.", latest.path);
+                        return load_hgnc_cache(&latest.path);
+                    }
+                }
+            }
+
+            if offline {
+                return Err(
+                    "No fresh cached HGNC release is available and --offline was set".into(),
+                );
             }
+
             // Otherwise, download and create cache
             println!("Downloading HGNC complete set into memory...");
-            let bytes = download_hgnc_complete_set_bytes()?;
-            let reader = BufReader::new(std::io::Cursor::new(bytes));
+            let downloaded = download_hgnc_complete_set_bytes()?;
+            let reader = BufReader::new(std::io::Cursor::new(downloaded.bytes));
             println!("Creating HGNC cache from downloaded data...");
-            create_hgnc_cache_from_reader(reader)?
+            let release_date = release_date_from_last_modified(&downloaded.validators.last_modified)
+                .unwrap_or_else(local_build_date);
+            let validators = ReleaseValidators {
+                release_date,
+                last_modified: downloaded.validators.last_modified,
+                etag: downloaded.validators.etag,
+            };
+            (create_hgnc_cache_from_reader(reader, overrides.as_ref())?, validators)
         }
     };
 
+    let bin_path = get_hgnc_bin_cache_path_for_release(&validators.release_date)?;
+
     println!("Dumping HGNC cache to: {:?}", bin_path);
-    dump_hgnc_cache(&cache, &bin_path)?;
+    dump_hgnc_cache(&cache, &validators, &bin_path)?;
 
     println!("Loading HGNC cache from: {:?}", bin_path);
     let archived_cache = load_hgnc_cache(&bin_path)?;
@@ -219,3 +802,184 @@ pub fn get_hgnc_cache<P: AsRef<Path>>(
 
     Ok(archived_cache)
 }
+
+#[cfg(test)]
+mod lookup_entry_tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_index_is_not_pushed_twice() {
+        let mut lookup = std::collections::HashMap::new();
+        add_lookup_entry(&mut lookup, "FOO".to_string(), 0);
+        add_lookup_entry(&mut lookup, "FOO".to_string(), 0);
+        assert_eq!(lookup["FOO"], vec![0]);
+    }
+
+    #[test]
+    fn distinct_indices_both_accumulate() {
+        let mut lookup = std::collections::HashMap::new();
+        add_lookup_entry(&mut lookup, "FOO".to_string(), 0);
+        add_lookup_entry(&mut lookup, "FOO".to_string(), 1);
+        assert_eq!(lookup["FOO"], vec![0, 1]);
+    }
+}
+
+#[cfg(test)]
+mod prune_tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn release(release_date: &str, size_bytes: u64, age_secs: u64) -> CachedRelease {
+        CachedRelease {
+            release_date: release_date.to_string(),
+            path: PathBuf::from(format!("{HGNC_BIN_PREFIX}{release_date}{HGNC_BIN_SUFFIX}")),
+            size_bytes,
+            modified: SystemTime::now() - Duration::from_secs(age_secs),
+        }
+    }
+
+    fn releases() -> Vec<CachedRelease> {
+        vec![
+            release("2024-01-01", 300, 300),
+            release("2024-06-01", 100, 100),
+            release("2024-03-01", 200, 200),
+        ]
+    }
+
+    #[test]
+    fn all_scope_selects_everything() {
+        let selected = select_releases_for_scope(releases(), &PruneScope::All);
+        assert_eq!(selected.len(), 3);
+    }
+
+    #[test]
+    fn oldest_sort_takes_highest_age_first() {
+        let scope = PruneScope::Group {
+            sort: PruneSort::Oldest,
+            n: 1,
+            invert: false,
+        };
+        let selected = select_releases_for_scope(releases(), &scope);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].release_date, "2024-01-01");
+    }
+
+    #[test]
+    fn largest_sort_takes_biggest_file_first() {
+        let scope = PruneScope::Group {
+            sort: PruneSort::Largest,
+            n: 1,
+            invert: false,
+        };
+        let selected = select_releases_for_scope(releases(), &scope);
+        assert_eq!(selected[0].release_date, "2024-01-01");
+    }
+
+    #[test]
+    fn alpha_sort_invert_takes_last_release_date() {
+        let scope = PruneScope::Group {
+            sort: PruneSort::Alpha,
+            n: 1,
+            invert: true,
+        };
+        let selected = select_releases_for_scope(releases(), &scope);
+        assert_eq!(selected[0].release_date, "2024-06-01");
+    }
+
+    #[test]
+    fn n_caps_how_many_are_selected() {
+        let scope = PruneScope::Group {
+            sort: PruneSort::Oldest,
+            n: 2,
+            invert: false,
+        };
+        let selected = select_releases_for_scope(releases(), &scope);
+        assert_eq!(selected.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod release_date_tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc2822_last_modified_header() {
+        let parsed = release_date_from_last_modified("Sat, 01 Jun 2024 00:00:00 GMT");
+        assert_eq!(parsed.as_deref(), Some("2024-06-01"));
+    }
+
+    #[test]
+    fn rejects_empty_header() {
+        assert_eq!(release_date_from_last_modified(""), None);
+    }
+
+    #[test]
+    fn rejects_malformed_header() {
+        assert_eq!(release_date_from_last_modified("not a date"), None);
+    }
+}
+
+#[cfg(test)]
+mod docket_tests {
+    use super::*;
+
+    fn sample_docket() -> CacheDocket {
+        CacheDocket {
+            format_version: CACHE_FORMAT_VERSION,
+            validators: ReleaseValidators {
+                release_date: "2024-06-01".to_string(),
+                last_modified: "Tue, 01 Jun 2024 00:00:00 GMT".to_string(),
+                etag: "\"abc123\"".to_string(),
+            },
+            content_hash: [7u8; CONTENT_HASH_LEN],
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trips() {
+        let docket = sample_docket();
+        let encoded = docket.encode();
+        let (decoded, body) = CacheDocket::decode(&encoded).unwrap();
+        assert!(body.is_empty());
+        assert_eq!(decoded.format_version, docket.format_version);
+        assert_eq!(
+            decoded.validators.release_date,
+            docket.validators.release_date
+        );
+        assert_eq!(
+            decoded.validators.last_modified,
+            docket.validators.last_modified
+        );
+        assert_eq!(decoded.validators.etag, docket.validators.etag);
+        assert_eq!(decoded.content_hash, docket.content_hash);
+    }
+
+    #[test]
+    fn decode_splits_off_trailing_body() {
+        let mut bytes = sample_docket().encode();
+        bytes.extend_from_slice(b"rest of the cache body");
+        let (_, body) = CacheDocket::decode(&bytes).unwrap();
+        assert_eq!(body, b"rest of the cache body");
+    }
+
+    #[test]
+    fn decode_rejects_truncated_header() {
+        let bytes = vec![0u8; DOCKET_LEN - 1];
+        assert!(CacheDocket::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_bad_magic() {
+        let mut bytes = sample_docket().encode();
+        bytes[0] = b'X';
+        assert!(CacheDocket::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_mismatched_format_version() {
+        let mut docket = sample_docket();
+        docket.format_version += 1;
+        let encoded = docket.encode();
+        assert!(CacheDocket::decode(&encoded).is_err());
+    }
+}
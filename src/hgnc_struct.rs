@@ -0,0 +1,306 @@
+use rkyv::{Archive, Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single HGNC gene record, mirroring the columns of `hgnc_complete_set.txt`.
+///
+/// Every field is a `u32` index into the parent `HgncCache`'s `string_pool` rather than the
+/// string itself, since most fields are empty or repeat across the tens of thousands of records
+/// (e.g. `status`, `locus_group`). Index `0` is reserved for the empty string. Use the
+/// `ArchivedHgncRecord` accessor methods (e.g. `symbol`) to resolve a field back to `&str`.
+#[derive(Archive, Deserialize, Serialize, Debug, Clone, Default)]
+#[rkyv(derive(Debug))]
+pub struct HgncRecord {
+    pub hgnc_id: u32,
+    pub symbol: u32,
+    pub name: u32,
+    pub locus_group: u32,
+    pub locus_type: u32,
+    pub status: u32,
+    pub location: u32,
+    pub location_sortable: u32,
+    pub alias_symbol: u32,
+    pub alias_name: u32,
+    pub prev_symbol: u32,
+    pub prev_name: u32,
+    pub gene_group: u32,
+    pub gene_group_id: u32,
+    pub date_approved_reserved: u32,
+    pub date_symbol_changed: u32,
+    pub date_name_changed: u32,
+    pub date_modified: u32,
+    pub entrez_id: u32,
+    pub ensembl_gene_id: u32,
+    pub vega_id: u32,
+    pub ucsc_id: u32,
+    pub ena: u32,
+    pub refseq_accession: u32,
+    pub ccds_id: u32,
+    pub uniprot_ids: u32,
+    pub pubmed_id: u32,
+    pub mgd_id: u32,
+    pub rgd_id: u32,
+    pub lsdb: u32,
+    pub cosmic: u32,
+    pub omim_id: u32,
+    pub mirbase: u32,
+    pub homeodb: u32,
+    pub snornabase: u32,
+    pub bioparadigms_slc: u32,
+    pub orphanet: u32,
+    pub pseudogene_org: u32,
+    pub horde_id: u32,
+    pub merops: u32,
+    pub imgt: u32,
+    pub iuphar: u32,
+    pub kznf_gene_catalog: u32,
+    pub mamit_trnadb: u32,
+    pub cd: u32,
+    pub lncrnadb: u32,
+    pub enzyme_id: u32,
+    pub intermediate_filament_db: u32,
+    pub rna_central_id: u32,
+    pub lncipedia: u32,
+    pub gtrnadb: u32,
+    pub agr: u32,
+    pub mane_select: u32,
+    pub gencc: u32,
+}
+
+/// In-memory HGNC lookup table: every record, a deduplicated pool of the strings they reference,
+/// and a case-insensitive symbol/alias/previous-symbol index into `records`.
+///
+/// `lookup` maps to a `Vec` rather than a single index because alias/previous-symbol collisions
+/// across records are common; a symbol with more than one candidate is ambiguous unless a
+/// `config_layer::ConfigLayer` override collapses it to one.
+#[derive(Archive, Deserialize, Serialize, Debug)]
+#[rkyv(derive(Debug))]
+pub struct HgncCache {
+    pub records: Vec<HgncRecord>,
+    pub lookup: HashMap<String, Vec<usize>>,
+    pub string_pool: Vec<String>,
+}
+
+impl ArchivedHgncCache {
+    /// Resolves a string-pool index stored on an `ArchivedHgncRecord` field back to `&str`.
+    pub fn resolve(&self, index: u32) -> &str {
+        self.string_pool[index as usize].as_str()
+    }
+}
+
+impl ArchivedHgncRecord {
+    pub fn hgnc_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.hgnc_id.to_native())
+    }
+
+    pub fn symbol<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.symbol.to_native())
+    }
+
+    pub fn name<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.name.to_native())
+    }
+
+    pub fn locus_group<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.locus_group.to_native())
+    }
+
+    pub fn locus_type<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.locus_type.to_native())
+    }
+
+    pub fn status<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.status.to_native())
+    }
+
+    pub fn location<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.location.to_native())
+    }
+
+    pub fn location_sortable<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.location_sortable.to_native())
+    }
+
+    pub fn alias_symbol<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.alias_symbol.to_native())
+    }
+
+    pub fn alias_name<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.alias_name.to_native())
+    }
+
+    pub fn prev_symbol<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.prev_symbol.to_native())
+    }
+
+    pub fn prev_name<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.prev_name.to_native())
+    }
+
+    pub fn gene_group<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.gene_group.to_native())
+    }
+
+    pub fn gene_group_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.gene_group_id.to_native())
+    }
+
+    pub fn date_approved_reserved<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.date_approved_reserved.to_native())
+    }
+
+    pub fn date_symbol_changed<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.date_symbol_changed.to_native())
+    }
+
+    pub fn date_name_changed<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.date_name_changed.to_native())
+    }
+
+    pub fn date_modified<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.date_modified.to_native())
+    }
+
+    pub fn entrez_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.entrez_id.to_native())
+    }
+
+    pub fn ensembl_gene_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.ensembl_gene_id.to_native())
+    }
+
+    pub fn vega_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.vega_id.to_native())
+    }
+
+    pub fn ucsc_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.ucsc_id.to_native())
+    }
+
+    pub fn ena<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.ena.to_native())
+    }
+
+    pub fn refseq_accession<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.refseq_accession.to_native())
+    }
+
+    pub fn ccds_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.ccds_id.to_native())
+    }
+
+    pub fn uniprot_ids<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.uniprot_ids.to_native())
+    }
+
+    pub fn pubmed_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.pubmed_id.to_native())
+    }
+
+    pub fn mgd_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.mgd_id.to_native())
+    }
+
+    pub fn rgd_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.rgd_id.to_native())
+    }
+
+    pub fn lsdb<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.lsdb.to_native())
+    }
+
+    pub fn cosmic<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.cosmic.to_native())
+    }
+
+    pub fn omim_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.omim_id.to_native())
+    }
+
+    pub fn mirbase<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.mirbase.to_native())
+    }
+
+    pub fn homeodb<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.homeodb.to_native())
+    }
+
+    pub fn snornabase<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.snornabase.to_native())
+    }
+
+    pub fn bioparadigms_slc<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.bioparadigms_slc.to_native())
+    }
+
+    pub fn orphanet<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.orphanet.to_native())
+    }
+
+    pub fn pseudogene_org<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.pseudogene_org.to_native())
+    }
+
+    pub fn horde_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.horde_id.to_native())
+    }
+
+    pub fn merops<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.merops.to_native())
+    }
+
+    pub fn imgt<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.imgt.to_native())
+    }
+
+    pub fn iuphar<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.iuphar.to_native())
+    }
+
+    pub fn kznf_gene_catalog<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.kznf_gene_catalog.to_native())
+    }
+
+    pub fn mamit_trnadb<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.mamit_trnadb.to_native())
+    }
+
+    pub fn cd<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.cd.to_native())
+    }
+
+    pub fn lncrnadb<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.lncrnadb.to_native())
+    }
+
+    pub fn enzyme_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.enzyme_id.to_native())
+    }
+
+    pub fn intermediate_filament_db<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.intermediate_filament_db.to_native())
+    }
+
+    pub fn rna_central_id<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.rna_central_id.to_native())
+    }
+
+    pub fn lncipedia<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.lncipedia.to_native())
+    }
+
+    pub fn gtrnadb<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.gtrnadb.to_native())
+    }
+
+    pub fn agr<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.agr.to_native())
+    }
+
+    pub fn mane_select<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.mane_select.to_native())
+    }
+
+    pub fn gencc<'a>(&self, cache: &'a ArchivedHgncCache) -> &'a str {
+        cache.resolve(self.gencc.to_native())
+    }
+}
@@ -1,20 +1,59 @@
+pub mod config_layer;
 pub mod hgnc_cache_functions;
 pub mod hgnc_struct;
 
 use crate::hgnc_struct::{ArchivedHgncCache, ArchivedHgncRecord};
 use std::error::Error;
+use std::fmt;
+
+/// Failure modes for [`query_lookup_table`].
+#[derive(Debug)]
+pub enum LookupError {
+    /// No record's symbol, alias, or previous symbol matched the query.
+    NotFound { query: String },
+    /// More than one record matched the query and no config-layer override resolved it.
+    /// `candidates` lists the HGNC ID of every matching record.
+    AmbiguousMatch {
+        query: String,
+        candidates: Vec<String>,
+    },
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::NotFound { query } => write!(f, "Query '{}' not found in cache", query),
+            LookupError::AmbiguousMatch { query, candidates } => write!(
+                f,
+                "Query '{}' is ambiguous: matches {}",
+                query,
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for LookupError {}
 
 pub fn query_lookup_table(
     query: String,
     cache: &ArchivedHgncCache,
 ) -> Result<&ArchivedHgncRecord, Box<dyn Error>> {
-    let idx = cache.lookup.get(query.to_uppercase().as_str());
-
-    match idx {
-        Some(&index) => {
-            let native_index = index.to_native() as usize;
-            Ok(&cache.records[native_index])
+    match cache.lookup.get(query.to_uppercase().as_str()) {
+        None => Err(Box::new(LookupError::NotFound { query })),
+        Some(indices) if indices.is_empty() => Err(Box::new(LookupError::NotFound { query })),
+        Some(indices) if indices.len() == 1 => {
+            Ok(&cache.records[indices[0].to_native() as usize])
+        }
+        Some(indices) => {
+            let candidates = indices
+                .iter()
+                .map(|index| {
+                    let record = &cache.records[index.to_native() as usize];
+                    record.hgnc_id(cache).to_string()
+                })
+                .collect();
+            Err(Box::new(LookupError::AmbiguousMatch { query, candidates }))
         }
-        None => Err(format!("Query '{}' not found in cache", query).into()),
     }
 }
@@ -1,10 +1,11 @@
-use clap::Parser;
-use hgnc_lookup::{hgnc_cache_functions, hgnc_struct, query_lookup_table};
+use clap::{Parser, Subcommand};
+use hgnc_lookup::hgnc_cache_functions::{self, PruneScope, PruneSort};
+use hgnc_lookup::{hgnc_struct, query_lookup_table};
 use std::error::Error;
 use std::io;
 use std::io::BufRead;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -26,22 +27,114 @@ struct Cli {
     #[arg(long)]
     force_rebuild: bool,
 
-    /// Delete the cache file and exit
+    /// Delete every cached HGNC release and exit
     #[arg(long)]
     delete_cache: bool,
+
+    /// Treat an existing cache older than this as stale without even checking the remote
+    /// (e.g. "12h", "7d")
+    #[arg(long, value_parser = humantime::parse_duration, value_name = "DURATION")]
+    max_age: Option<Duration>,
+
+    /// Skip all network access: reuse the newest cache on disk unless --max-age marks it stale,
+    /// and fail instead of downloading when no usable cache exists
+    #[arg(long)]
+    offline: bool,
+
+    /// Path to an INI-like `[alias]` override file (supports `%include`/`%unset`) merged over
+    /// the lookup table when the cache is built
+    #[arg(long = "override-file", value_name = "PATH")]
+    override_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List cached HGNC releases
+    List,
+    /// Delete cached HGNC releases
+    Prune(PruneArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct PruneArgs {
+    /// Delete every cached release
+    #[arg(long)]
+    all: bool,
+
+    /// Sort key used to choose which releases to prune
+    #[arg(long, value_enum, default_value_t = PruneSort::Oldest)]
+    sort: PruneSort,
+
+    /// Number of releases to delete after sorting
+    #[arg(long, default_value_t = 1)]
+    n: usize,
+
+    /// Reverse the sort order before taking `n`
+    #[arg(long)]
+    invert: bool,
 }
 
-fn maybe_delete_cache_bin(force: bool) -> Result<(), Box<dyn Error>> {
-    if !force {
+impl PruneArgs {
+    fn into_scope(self) -> PruneScope {
+        if self.all {
+            PruneScope::All
+        } else {
+            PruneScope::Group {
+                sort: self.sort,
+                n: self.n,
+                invert: self.invert,
+            }
+        }
+    }
+}
+
+fn run_list() -> Result<(), Box<dyn Error>> {
+    let releases = hgnc_cache_functions::list_cached_releases()?;
+    if releases.is_empty() {
+        println!("No cached HGNC releases found.");
         return Ok(());
     }
 
-    let bin_path = hgnc_cache_functions::get_hgnc_bin_cache_path()?;
-    if bin_path.exists() {
-        std::fs::remove_file(&bin_path)?;
-        eprintln!("Deleted cache file at: {:?}", bin_path);
+    println!("{:<14}{:>12}  MODIFIED", "RELEASE", "SIZE");
+    for release in releases {
+        let modified: chrono::DateTime<chrono::Local> = release.modified.into();
+        println!(
+            "{:<14}{:>12}  {}",
+            release.release_date,
+            release.size_bytes,
+            modified.format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+    Ok(())
+}
+
+fn run_prune(args: PruneArgs) -> Result<(), Box<dyn Error>> {
+    let removed = hgnc_cache_functions::prune_cached_releases(&args.into_scope())?;
+    if removed.is_empty() {
+        println!("No cached HGNC releases matched the prune scope.");
     } else {
-        eprintln!("No cache file to delete at: {:?}", bin_path);
+        for release in &removed {
+            println!("Deleted cached release: {}", release.release_date);
+        }
+    }
+    Ok(())
+}
+
+fn maybe_delete_cache(delete_cache: bool) -> Result<(), Box<dyn Error>> {
+    if !delete_cache {
+        return Ok(());
+    }
+
+    let removed = hgnc_cache_functions::prune_cached_releases(&PruneScope::All)?;
+    if removed.is_empty() {
+        eprintln!("No cache files to delete.");
+    } else {
+        for release in &removed {
+            eprintln!("Deleted cache file for release {}", release.release_date);
+        }
     }
     Ok(())
 }
@@ -62,7 +155,9 @@ fn run_interactive(
             Ok(record) => {
                 println!(
                     "Found record: HGNC ID: {}, Symbol: {}, Name: {}",
-                    record.hgnc_id, record.symbol, record.name
+                    record.hgnc_id(hgnc_cache),
+                    record.symbol(hgnc_cache),
+                    record.name(hgnc_cache)
                 );
             }
             Err(e) => {
@@ -78,8 +173,14 @@ fn run_interactive(
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
 
-    // Delete cache first for rebuild if requested
-    maybe_delete_cache_bin(cli.force_rebuild || cli.delete_cache)?;
+    if let Some(command) = cli.command {
+        return match command {
+            Command::List => run_list(),
+            Command::Prune(args) => run_prune(args),
+        };
+    }
+
+    maybe_delete_cache(cli.delete_cache)?;
 
     // Exit if only deleting cache
     if cli.delete_cache {
@@ -89,8 +190,20 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Load cache
     let start = Instant::now();
     let hgnc_cache = match cli.set_file {
-        Some(ref path) => hgnc_cache_functions::get_hgnc_cache(Some(path))?,
-        None => hgnc_cache_functions::get_hgnc_cache::<PathBuf>(None)?,
+        Some(ref path) => hgnc_cache_functions::get_hgnc_cache(
+            Some(path),
+            cli.force_rebuild,
+            cli.max_age,
+            cli.offline,
+            cli.override_file.as_deref(),
+        )?,
+        None => hgnc_cache_functions::get_hgnc_cache::<PathBuf>(
+            None,
+            cli.force_rebuild,
+            cli.max_age,
+            cli.offline,
+            cli.override_file.as_deref(),
+        )?,
     };
     let duration = start.elapsed();
     println!("HGNC cache is ready. Load took: {:?}", duration);